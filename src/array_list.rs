@@ -0,0 +1,271 @@
+//! A fixed-capacity list backed by an array, for use before a heap is available.
+//! It offers the same API and contracts as `List`, but `push` hands the element back when the list is full.
+
+use prusti_contracts::*;
+
+use crate::external_spec::trusted_option::*;
+use crate::external_spec::trusted_result::*;
+use crate::range_trait::UniqueCheck;
+
+/// The elements are stored in the order they were pushed, so the head of the list
+/// (`lookup(0)`) is the last occupied slot and pushing or popping never moves other elements.
+#[invariant(self.len <= N)]
+#[invariant(forall(|i: usize| (i < N) ==> (self.elems[i].is_some() == (i < self.len))))]
+pub struct ArrayList<T, const N: usize> {
+    elems: [Option<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayList<T, N> {
+    const EMPTY_SLOT: Option<T> = None;
+
+    #[pure]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[pure]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[pure]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    #[pure]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    #[ensures(result.len() == 0)]
+    pub const fn new() -> Self {
+        ArrayList { elems: [Self::EMPTY_SLOT; N], len: 0 }
+    }
+
+    #[pure]
+    #[requires(index < self.len())]
+    pub fn lookup(&self, index: usize) -> &T {
+        peek_option_ref(&self.elems[self.len - 1 - index])
+    }
+
+    /// Pushes `elem` onto the head of the list, or hands it back if the list is full.
+    #[ensures(old(self.is_full()) ==>
+        result.is_err() &&
+        snap(peek_err_ref(&result)) === elem &&
+        self.len() == old(self.len()) &&
+        forall(|i: usize| (i < self.len()) ==>
+            old(self.lookup(i)) === self.lookup(i))
+    )]
+    #[ensures(!old(self.is_full()) ==>
+        result.is_ok() &&
+        self.len() == old(self.len()) + 1 &&
+        snap(self.lookup(0)) === elem &&
+        forall(|i: usize| (i < old(self.len())) ==>
+            old(self.lookup(i)) === self.lookup(i + 1))
+    )]
+    pub fn push(&mut self, elem: T) -> Result<(), T> {
+        if self.len == N {
+            return Err(elem);
+        }
+        self.elems[self.len] = Some(elem);
+        self.len += 1;
+        Ok(())
+    }
+
+    predicate! {
+        // the same two-state predicate as `List::head_removed`
+        fn head_removed(&self, prev: &Self) -> bool {
+            self.len() == prev.len() - 1
+            && forall(|i: usize|
+                (1 <= i && i < prev.len())
+                    ==> prev.lookup(i) === self.lookup(i - 1))
+        }
+    }
+
+    #[ensures(old(self.is_empty()) ==>
+        result.is_none() &&
+        self.is_empty()
+    )]
+    #[ensures(!old(self.is_empty()) ==>
+        self.head_removed(&old(snap(self))) &&
+        result === Some(snap(old(snap(self)).lookup(0)))
+    )]
+    pub fn try_pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        self.elems[self.len].take()
+    }
+
+    #[requires(!self.is_empty())]
+    #[ensures(self.head_removed(&old(snap(self))))]
+    #[ensures(result === old(snap(self)).lookup(0))]
+    pub fn pop(&mut self) -> T {
+        self.try_pop().unwrap()
+    }
+
+    #[pure]
+    #[requires(!self.is_empty())]
+    pub fn peek(&self) -> &T {
+        self.lookup(0)
+    }
+}
+
+#[refine_trait_spec]
+impl<T, const N: usize> Default for ArrayList<T, N> {
+    #[ensures(result.len() == 0)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The error returned by `ArrayList::push_unique`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PushUniqueError {
+    /// The list is full, so no element can be pushed.
+    Full,
+    /// The element overlaps the element at this index.
+    Overlaps(usize),
+}
+
+// The same predicates and contracts as for `List`; see there for the rationale.
+impl<T: UniqueCheck + Copy, const N: usize> ArrayList<T, N> {
+
+    #[pure]
+    #[requires(index < self.len())]
+    pub fn lookup_copy(&self, index: usize) -> T {
+        *self.lookup(index)
+    }
+
+    predicate! {
+        pub fn all_disjoint(&self) -> bool {
+            forall(|i: usize, j: usize| (i < j && j < self.len()) ==> {
+                let earlier = self.lookup_copy(j);
+                !earlier.overlaps(&self.lookup_copy(i))
+            })
+        }
+    }
+
+    /// Pushes `elem` if the list is not full and `elem` does not overlap any element in the list.
+    /// Otherwise returns `Full`, or `Overlaps` with the index of the first overlapping element,
+    /// and leaves the list unchanged.
+    #[requires(self.all_disjoint())]
+    #[ensures(self.all_disjoint())]
+    #[ensures(result.is_ok() ==>
+        self.len() == old(self.len()) + 1 &&
+        snap(self.lookup(0)) === elem &&
+        forall(|i: usize| (i < old(self.len())) ==>
+            old(self.lookup(i)) === self.lookup(i + 1))
+    )]
+    #[ensures(result.is_err() ==>
+        self.len() == old(self.len()) &&
+        forall(|i: usize| (i < self.len()) ==>
+            old(self.lookup(i)) === self.lookup(i))
+    )]
+    #[ensures(old(self.is_full()) ==> result === Err(PushUniqueError::Full))]
+    #[ensures(forall(|idx: usize| result === Err(PushUniqueError::Overlaps(idx)) ==>
+        idx < self.len() && self.lookup_copy(idx).overlaps(&elem)
+    ))]
+    pub fn push_unique(&mut self, elem: T) -> Result<(), PushUniqueError> {
+        if self.is_full() {
+            return Err(PushUniqueError::Full);
+        }
+        match self.elem_overlaps_in_list(elem, 0) {
+            Some(idx) => Err(PushUniqueError::Overlaps(idx)),
+            None => match self.push(elem) {
+                Ok(()) => Ok(()),
+                Err(_) => unreachable!(), // the list is not full
+            },
+        }
+    }
+
+    #[requires(0 <= index && index <= self.len())]
+    #[ensures(result.is_some() ==> peek_option(&result) < self.len())]
+    #[ensures(result.is_some() ==> {
+            let idx = peek_option(&result);
+            let range = self.lookup_copy(idx);
+            range.overlaps(&elem)
+        }
+    )]
+    #[ensures(result.is_none() ==>
+        forall(|i: usize| (index <= i && i < self.len()) ==> {
+            let range = self.lookup_copy(i);
+            !range.overlaps(&elem)
+        })
+    )]
+    pub(crate) fn elem_overlaps_in_list(&self, elem: T, index: usize) -> Option<usize> {
+        // `lookup` is O(1) here, so a plain index walk is enough.
+        let mut i = index;
+        while i < self.len() {
+            body_invariant!(index <= i && i < self.len());
+            // none of the elements checked so far overlap
+            body_invariant!(forall(|j: usize| (index <= j && j < i) ==> {
+                let range = self.lookup_copy(j);
+                !range.overlaps(&elem)
+            }));
+            if self.lookup_copy(i).overlaps(&elem) {
+                return Some(i);
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+#[cfg(prusti)]
+mod prusti_tests {
+    use super::*;
+
+    fn _test_array_list() {
+        let mut list: ArrayList<i32, 2> = ArrayList::new(); // create an new, empty list
+        prusti_assert!(list.is_empty() && list.len() == 0); // list should be empty
+
+        let a = list.push(5);
+        let b = list.push(10);
+        prusti_assert!(a.is_ok() && b.is_ok());
+        prusti_assert!(list.is_full() && list.len() == 2); // length correct
+
+        prusti_assert!(*list.lookup(0) == 10); // head is 10
+        prusti_assert!(*list.lookup(1) == 5); // 5 got pushed back correctly
+
+        let c = list.push(15);
+        prusti_assert!(c === Err(15)); // a full list hands the element back
+        prusti_assert!(list.len() == 2 && *list.lookup(0) == 10); // and is left unchanged
+
+        let x = list.pop();
+        prusti_assert!(x == 10); // pop returns the value that was added last
+        prusti_assert!(*list.peek() == 5);
+
+        match list.try_pop() {
+            Some(y) => assert!(y == 5),
+            None => unreachable!()
+        }
+
+        let z = list.try_pop();
+        prusti_assert!(list.is_empty() && list.len() == 0); // length correct
+        prusti_assert!(z.is_none()); // `try_pop` on an empty list should return `None`
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range::Range;
+
+    #[test]
+    fn full_list_hands_back_errors() {
+        let mut list: ArrayList<Range, 2> = ArrayList::new();
+        assert_eq!(list.push_unique(Range::new(0, 9).unwrap()), Ok(()));
+        assert_eq!(list.push_unique(Range::new(5, 14).unwrap()), Err(PushUniqueError::Overlaps(0)));
+        assert_eq!(list.push_unique(Range::new(10, 19).unwrap()), Ok(()));
+        assert!(list.is_full());
+
+        // a full list reports `Full`, even for an element that overlaps nothing
+        assert_eq!(list.push_unique(Range::new(20, 29).unwrap()), Err(PushUniqueError::Full));
+        assert!(list.push(Range::new(20, 29).unwrap()).is_err());
+        assert_eq!(list.len(), 2);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod trusted_option;
+pub mod trusted_partial_ord;
 pub mod trusted_result;
\ No newline at end of file
@@ -52,3 +52,21 @@ pub(crate) fn peek_option_ref<T>(val: &Option<T>) -> &T {
         None => unreachable!(),
     }
 }
+
+#[pure]
+#[requires(val.is_some())]
+pub(crate) fn peek_option_deref<'a, T>(val: &Option<&'a T>) -> &'a T {
+    match val {
+        Some(val) => val,
+        None => unreachable!(),
+    }
+}
+
+#[pure]
+#[requires(val.is_some())]
+pub(crate) fn peek_option_deref_mut<'a, T>(val: &'a Option<&mut T>) -> &'a T {
+    match val {
+        Some(val) => val,
+        None => unreachable!(),
+    }
+}
@@ -0,0 +1,28 @@
+//! The specification for the `PartialOrd` trait, so that comparisons of generic values can be used in pure functions.
+
+use prusti_contracts::*;
+
+#[extern_spec(core::cmp)]
+trait PartialOrd<#[generic] Rhs: ?Sized> {
+    #[pure]
+    fn lt(&self, other: &Rhs) -> bool;
+
+    #[pure]
+    fn le(&self, other: &Rhs) -> bool;
+
+    #[pure]
+    fn gt(&self, other: &Rhs) -> bool;
+
+    #[pure]
+    fn ge(&self, other: &Rhs) -> bool;
+}
+
+/// Lemma: the comparisons of an `Ord` type form a total order.
+/// Prusti does not know the laws of `Ord`, so they are assumed here,
+/// and calling the lemma makes them available to the verifier at the call site.
+#[trusted]
+#[ensures(forall(|a: T, b: T|
+    (a <= b || b <= a) && (a < b) == !(b <= a) && (a > b) == !(a <= b)
+))]
+#[ensures(forall(|a: T, b: T, c: T| (a <= b && b <= c) ==> a <= c))]
+pub(crate) fn total_order<T: Ord + Copy>() {}
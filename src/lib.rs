@@ -0,0 +1,51 @@
+//! A formally verified singly linked list, and a list of non-overlapping ranges built on top of it.
+//!
+//! The specifications are checked with Prusti. A plain `cargo build` only needs `prusti-contracts`,
+//! whose attributes expand to nothing outside of Prusti; the `verification` feature adds the
+//! Prusti specifications of the standard library.
+//!
+//! The crate is `no_std` and only needs `alloc`, so it can be used in a kernel;
+//! the `std` feature links the standard library instead.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+#![allow(dead_code)]
+#![allow(unused_imports)]
+// Prusti-friendly code (`matches!` in pure functions, negated comparisons) trips these lints
+#![allow(clippy::redundant_pattern_matching)]
+#![allow(clippy::neg_cmp_op_on_partial_ord)]
+
+extern crate alloc;
+extern crate prusti_contracts;
+// used by `impl_step_for_newtype!` in other crates
+#[doc(hidden)]
+pub extern crate prusti_contracts as __prusti_contracts;
+#[cfg(feature = "verification")]
+extern crate prusti_std;
+
+mod array_list;
+mod external_spec;
+mod linked_list;
+mod range;
+mod range_inclusive;
+mod range_trait;
+mod step;
+
+use prusti_contracts::*;
+
+pub use allocator_api2::alloc::{AllocError, Allocator, Global};
+pub use array_list::{ArrayList, PushUniqueError};
+pub use linked_list::{IntoIter, Iter, IterMut, List};
+pub use range::{GenericRange, Range, RangeError};
+pub use range_inclusive::{Indices, RangeInclusive};
+pub use range_trait::UniqueCheck;
+pub use step::Step;
+
+/// Adds the given range to the list if it does not overlap with any of the ranges in the list,
+/// otherwise returns the index of an overlapping range.
+/// The ranges in the list stay pairwise disjoint.
+#[requires(chunk_list.all_disjoint())]
+#[ensures(chunk_list.all_disjoint())]
+pub fn can_create_new<U: Copy + PartialOrd>(chunk_range: GenericRange<U>, chunk_list: &mut List<GenericRange<U>>) -> Result<(), usize> {
+    chunk_list.push_unique(chunk_range)
+}
@@ -0,0 +1,1250 @@
+use prusti_contracts::*;
+
+use allocator_api2::alloc::{AllocError, Allocator, Global};
+use allocator_api2::boxed::Box;
+
+use crate::external_spec::trusted_option::*;
+use crate::external_spec::trusted_result::*;
+use crate::range_trait::UniqueCheck;
+
+/// The length is cached in `len` so that `len()` is O(1);
+/// the invariant ties it to `link_len`, which remains the specification of the length.
+/// All nodes are allocated with `alloc`, which defaults to the global allocator.
+#[invariant(self.len == link_len(&self.head))]
+pub struct List<T, A: Allocator + Clone = Global> {
+    head: Link<T, A>,
+    len: usize,
+    alloc: A,
+}
+
+type Link<T, A> = Option<Box<Node<T, A>, A>>;
+
+struct Node<T, A: Allocator> {
+    elem: T,
+    next: Link<T, A>,
+}
+
+impl<T> List<T> {
+    #[ensures(result.len() == 0)]
+    pub fn new() -> Self {
+        List::new_in(Global)
+    }
+}
+
+impl<T, A: Allocator + Clone> List<T, A> {
+    #[pure]
+    #[ensures(result == link_len(&self.head))]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[pure]
+    pub fn is_empty(&self) -> bool {
+        matches!(self.head, None)
+    }
+
+    #[ensures(result.len() == 0)]
+    pub fn new_in(alloc: A) -> Self {
+        List { head: None, len: 0, alloc }
+    }
+
+    /// The allocator the nodes of this list are allocated with.
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    #[pure]
+    #[requires(index < self.len())]
+    pub fn lookup(&self, index: usize) -> &T {
+        link_lookup(&self.head, index)
+    }
+
+    #[ensures(self.len() == old(self.len()) + 1)]
+    #[ensures(snap(self.lookup(0)) === elem)]
+    #[ensures(forall(|i: usize| (i < old(self.len())) ==>
+                 old(self.lookup(i)) === self.lookup(i + 1)))]
+    pub fn push(&mut self, elem: T) {
+        let new_node = Box::new_in(Node {
+            elem,
+            next: self.head.take(),
+        }, self.alloc.clone());
+
+        self.head = Some(new_node);
+        self.len += 1;
+    }
+
+    /// Like `push`, but hands `elem` back together with the error instead of aborting
+    /// if the node cannot be allocated. A failed allocation leaves the list unchanged.
+    #[ensures(result.is_ok() ==>
+        self.len() == old(self.len()) + 1 &&
+        snap(self.lookup(0)) === elem &&
+        forall(|i: usize| (i < old(self.len())) ==>
+            old(self.lookup(i)) === self.lookup(i + 1))
+    )]
+    #[ensures(result.is_err() ==>
+        self.len() == old(self.len()) &&
+        forall(|i: usize| (i < self.len()) ==>
+            old(self.lookup(i)) === self.lookup(i)) &&
+        snap(&peek_err_ref(&result).0) === elem
+    )]
+    pub fn try_push(&mut self, elem: T) -> Result<(), (T, AllocError)> {
+        let new_node = Node {
+            elem,
+            next: self.head.take(),
+        };
+
+        match try_box_node(new_node, self.alloc.clone()) {
+            Ok(new_node) => {
+                self.head = Some(new_node);
+                self.len += 1;
+                Ok(())
+            }
+            Err(node) => {
+                self.head = node.next;
+                Err((node.elem, AllocError))
+            }
+        }
+    }
+
+    predicate! {
+        // two-state predicate to check if the head of a list was correctly removed
+        fn head_removed(&self, prev: &Self) -> bool {
+            self.len() == prev.len() - 1 // The length will decrease by 1
+            && forall(|i: usize| // Every element will be shifted forwards by one
+                (1 <= i && i < prev.len())
+                    ==> prev.lookup(i) === self.lookup(i - 1))
+        }
+    }
+
+    #[ensures(old(self.is_empty()) ==>
+        result.is_none() &&
+        self.is_empty()
+    )]
+    #[ensures(!old(self.is_empty()) ==>
+        self.head_removed(&old(snap(self))) &&
+        result === Some(snap(old(snap(self)).lookup(0)))
+    )]
+    pub fn try_pop(&mut self) -> Option<T> {
+        match self.head.take() {
+            None => None,
+            Some(node) => {
+                let node = unbox_node(node);
+                self.head = node.next;
+                self.len -= 1;
+                Some(node.elem)
+            }
+        }
+    }
+
+    #[requires(!self.is_empty())]
+    #[ensures(self.head_removed(&old(snap(self))))]
+    #[ensures(result === old(snap(self)).lookup(0))]
+    pub fn pop(&mut self) -> T {
+        self.try_pop().unwrap()
+    }
+
+    predicate! {
+        // two-state predicate to check if an element was correctly inserted at `index`
+        pub fn elem_inserted(&self, prev: &Self, index: usize) -> bool {
+            self.len() == prev.len() + 1 // The length will increase by 1
+            && forall(|i: usize| // Every element before `index` is unchanged
+                (i < index)
+                    ==> prev.lookup(i) === self.lookup(i))
+            && forall(|i: usize| // Every element from `index` on will be shifted backwards by one
+                (index <= i && i < prev.len())
+                    ==> prev.lookup(i) === self.lookup(i + 1))
+        }
+    }
+
+    predicate! {
+        // two-state predicate to check if the element at `index` was correctly removed
+        pub fn elem_removed(&self, prev: &Self, index: usize) -> bool {
+            self.len() == prev.len() - 1 // The length will decrease by 1
+            && forall(|i: usize| // Every element before `index` is unchanged
+                (i < index)
+                    ==> prev.lookup(i) === self.lookup(i))
+            && forall(|i: usize| // Every element after `index` will be shifted forwards by one
+                (index < i && i < prev.len())
+                    ==> prev.lookup(i) === self.lookup(i - 1))
+        }
+    }
+
+    #[requires(index <= self.len())]
+    #[ensures(self.elem_inserted(&old(snap(self)), index))]
+    #[ensures(snap(self.lookup(index)) === elem)]
+    pub fn insert(&mut self, index: usize, elem: T) {
+        link_insert(&mut self.head, index, elem, &self.alloc);
+        self.len += 1;
+    }
+
+    #[requires(index < self.len())]
+    #[ensures(self.elem_removed(&old(snap(self)), index))]
+    #[ensures(result === old(snap(self)).lookup(index))]
+    pub fn remove(&mut self, index: usize) -> T {
+        let elem = link_remove(&mut self.head, index);
+        self.len -= 1;
+        elem
+    }
+
+    /// Moves all elements of `other` to the end of `self`, leaving `other` empty.
+    #[ensures(self.len() == old(self.len()) + old(other.len()))]
+    #[ensures(other.len() == 0)]
+    #[ensures(forall(|i: usize| (i < old(self.len())) ==>
+                 old(self.lookup(i)) === self.lookup(i)))]
+    #[ensures(forall(|i: usize| (i < old(other.len())) ==>
+                 old(other.lookup(i)) === self.lookup(old(self.len()) + i)))]
+    pub fn append(&mut self, other: &mut List<T, A>) {
+        let other_head = other.head.take();
+        link_append(&mut self.head, other_head);
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    /// Splits the list in two at `at`.
+    /// `self` keeps the elements `0..at` and the returned list holds the elements `at..len`.
+    #[requires(at <= self.len())]
+    #[ensures(self.len() == at)]
+    #[ensures(result.len() == old(self.len()) - at)]
+    #[ensures(forall(|i: usize| (i < at) ==>
+                 old(self.lookup(i)) === self.lookup(i)))]
+    #[ensures(forall(|i: usize| (i < result.len()) ==>
+                 old(self.lookup(at + i)) === result.lookup(i)))]
+    pub fn split_off(&mut self, at: usize) -> List<T, A> {
+        let tail_len = self.len - at;
+        let tail_head = link_split_off(&mut self.head, at);
+        self.len = at;
+        List { head: tail_head, len: tail_len, alloc: self.alloc.clone() }
+    }
+
+    /// Reverses the order of the elements in place by relinking the nodes.
+    #[ensures(self.len() == old(self.len()))]
+    #[ensures(forall(|i: usize| (i < self.len()) ==>
+                 old(self.lookup(i)) === self.lookup(self.len() - 1 - i)))]
+    pub fn reverse(&mut self) {
+        // The length does not change, so `self.len` is left as is
+        // and the loop reasons about the links directly.
+        let mut rest = self.head.take();
+        while rest.is_some() {
+            body_invariant!(self.len == old(self.len()));
+            body_invariant!(link_len(&rest) <= old(self.len()));
+            body_invariant!(link_len(&self.head) == old(self.len()) - link_len(&rest));
+            // `rest` is the tail of the original list that has not been moved yet
+            body_invariant!(forall(|i: usize| (i < link_len(&rest)) ==>
+                link_lookup(&rest, i) === old(self.lookup(i + (old(self.len()) - link_len(&rest))))));
+            // the moved nodes are in reverse order
+            body_invariant!(forall(|i: usize| (i < link_len(&self.head)) ==>
+                link_lookup(&self.head, i) === old(self.lookup(link_len(&self.head) - 1 - i))));
+            let mut node = rest.unwrap();
+            rest = node.next.take();
+            node.next = self.head.take();
+            self.head = Some(node);
+        }
+    }
+
+    /// Pushes every element of `other` onto `self`, starting with the head of `other`.
+    /// Since `push` prepends, the elements of `other` end up in reverse order in front of the old elements.
+    #[ensures(self.len() == old(self.len()) + old(other.len()))]
+    #[ensures(forall(|i: usize| (i < old(other.len())) ==>
+                 old(other.lookup(i)) === self.lookup(old(other.len()) - 1 - i)))]
+    #[ensures(forall(|i: usize| (i < old(self.len())) ==>
+                 old(self.lookup(i)) === self.lookup(i + old(other.len()))))]
+    pub fn push_all<B: Allocator + Clone>(&mut self, mut other: List<T, B>) {
+        while !other.is_empty() {
+            body_invariant!(other.len() <= old(other.len()));
+            body_invariant!(self.len() == old(self.len()) + (old(other.len()) - other.len()));
+            // the rest of `other` is the tail of the original `other`
+            body_invariant!(forall(|i: usize| (i < other.len()) ==>
+                other.lookup(i) === old(other.lookup(i + (old(other.len()) - other.len())))));
+            // the popped elements were pushed in reverse order
+            body_invariant!(forall(|i: usize| (i < old(other.len()) - other.len()) ==>
+                old(other.lookup(i)) === self.lookup(old(other.len()) - other.len() - 1 - i)));
+            // the old elements of `self` were shifted back by the number of pushed elements
+            body_invariant!(forall(|i: usize| (i < old(self.len())) ==>
+                old(self.lookup(i)) === self.lookup(i + (old(other.len()) - other.len()))));
+            let elem = other.pop();
+            self.push(elem);
+        }
+    }
+
+    /// Pushes the elements of `items` in order, like `Extend` does for an iterator,
+    /// so they end up in reverse order in front of the old elements.
+    #[ensures(self.len() == old(self.len()) + items.len())]
+    #[ensures(forall(|k: usize| (k < items.len()) ==>
+                 snap(self.lookup(items.len() - 1 - k)) === items[k]))]
+    #[ensures(forall(|i: usize| (i < old(self.len())) ==>
+                 old(self.lookup(i)) === self.lookup(i + items.len())))]
+    pub fn extend_from_slice(&mut self, items: &[T])
+    where
+        T: Copy,
+    {
+        let mut k = 0;
+        while k < items.len() {
+            body_invariant!(k < items.len());
+            body_invariant!(self.len() == old(self.len()) + k);
+            // the pushed elements are in reverse order
+            body_invariant!(forall(|j: usize| (j < k) ==>
+                snap(self.lookup(k - 1 - j)) === items[j]));
+            // the old elements of `self` were shifted back by the number of pushed elements
+            body_invariant!(forall(|i: usize| (i < old(self.len())) ==>
+                old(self.lookup(i)) === self.lookup(i + k)));
+            self.push(items[k]);
+            k += 1;
+        }
+    }
+
+    #[pure]
+    #[requires(!self.is_empty())]
+    pub fn peek(&self) -> &T {
+        self.lookup(0)
+    }
+
+    #[trusted] // required due to unsupported reference in enum
+    #[requires(!self.is_empty())]
+    #[ensures(snap(result) === old(snap(self.peek())))]
+    #[after_expiry(
+        old(self.len()) === self.len() // (1. condition)
+        && forall(|i: usize| 1 <= i && i < self.len() // (2. condition)
+            ==> old(snap(self.lookup(i))) === snap(self.lookup(i)))
+        && snap(self.peek()) === before_expiry(snap(result)) // (3. condition)
+    )]
+    pub fn peek_mut(&mut self) -> &mut T {
+        // This does not work in Prusti at the moment:
+        // "&mut self.head" has type "&mut Option<T>"
+        // this gets auto-dereferenced by Rust into type: "Option<&mut T>"
+        // this then gets matched to "Some(node: &mut T)"
+        // References in enums are not yet supported, so this cannot be verified by Prusti
+        if let Some(node) = &mut self.head {
+            &mut node.elem
+        } else {
+            unreachable!()
+        }
+        // ...
+    }
+
+    #[trusted] // required due to unsupported reference in enum
+    #[ensures(self.is_empty() ==> result.is_none())]
+    #[ensures(!self.is_empty() ==>
+        result.is_some() &&
+        snap(peek_option_deref(&result)) === snap(self.lookup(0))
+    )]
+    pub fn try_peek(&self) -> Option<&T> {
+        match &self.head {
+            Some(node) => Some(&node.elem),
+            None => None,
+        }
+    }
+
+    #[trusted] // required due to unsupported reference in enum
+    #[ensures(old(self.is_empty()) ==> result.is_none())]
+    #[ensures(!old(self.is_empty()) ==>
+        result.is_some() &&
+        snap(peek_option_deref_mut(&result)) === old(snap(self.lookup(0)))
+    )]
+    #[after_expiry(
+        old(self.len()) === self.len() // (1. condition)
+        && forall(|i: usize| 1 <= i && i < self.len() // (2. condition)
+            ==> old(snap(self.lookup(i))) === snap(self.lookup(i)))
+        && (!self.is_empty() ==> // (3. condition)
+            snap(self.lookup(0)) === before_expiry(snap(peek_option_deref_mut(&result))))
+    )]
+    pub fn try_peek_mut(&mut self) -> Option<&mut T> {
+        match &mut self.head {
+            Some(node) => Some(&mut node.elem),
+            None => None,
+        }
+    }
+
+    /// Like `peek_mut`, but for the element at `index`.
+    /// Unlike `iter_mut`, the pledge also states the final value of the changed element.
+    #[trusted] // required due to unsupported reference in enum
+    #[requires(index < self.len())]
+    #[ensures(snap(result) === old(snap(self.lookup(index))))]
+    #[after_expiry(
+        old(self.len()) === self.len() // (1. condition)
+        && forall(|i: usize| i < self.len() && i != index // (2. condition)
+            ==> old(snap(self.lookup(i))) === snap(self.lookup(i)))
+        && snap(self.lookup(index)) === before_expiry(snap(result)) // (3. condition)
+    )]
+    pub fn lookup_mut(&mut self, index: usize) -> &mut T {
+        link_lookup_mut(&mut self.head, index)
+    }
+
+    #[ensures(result.index() == 0)]
+    #[ensures(snap(result.list()) === snap(self))]
+    pub fn iter(&self) -> Iter<'_, T, A> {
+        Iter {
+            list: self,
+            next: &self.head,
+            index: 0,
+        }
+    }
+
+    /// Returns an iterator that yields a mutable reference to each element, from the head to the tail.
+    ///
+    /// The pledge only covers the elements that were not yielded: after the iterator expires,
+    /// the verifier knows nothing about an element that was yielded, even if it was never written,
+    /// since the final value behind a yielded reference cannot be tracked per element.
+    /// Use `lookup_mut` when the new value of an element matters, e.g., to resize a chunk in place.
+    #[trusted] // required due to unsupported reference in enum
+    #[ensures(result.index() == 0)]
+    #[ensures(result.list_len() == old(self.len()))]
+    #[ensures(forall(|i: usize| i < result.list_len() ==>
+        snap(result.lookup(i)) === old(snap(self.lookup(i)))))]
+    #[after_expiry(
+        old(self.len()) === self.len() // (1. condition)
+        && forall(|i: usize| before_expiry(result.index()) <= i && i < self.len() // (2. condition)
+            ==> old(snap(self.lookup(i))) === snap(self.lookup(i)))
+    )]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, A> {
+        IterMut {
+            next: self.head.as_deref_mut(),
+            index: 0,
+            list_len: self.len,
+        }
+    }
+}
+
+#[pure]
+#[requires(index < link_len(link))]
+fn link_lookup<T, A: Allocator>(link: &Link<T, A>, index: usize) -> &T {
+    match link {
+        Some(node) => {
+            if index == 0 {
+                &node.elem
+            } else {
+                link_lookup(&node.next, index - 1)
+            }
+        }
+        None => unreachable!(),
+    }
+}
+
+#[trusted] // required due to unsupported reference in enum
+#[requires(index < link_len(link))]
+fn link_lookup_mut<T, A: Allocator>(link: &mut Link<T, A>, index: usize) -> &mut T {
+    match link {
+        Some(node) => {
+            if index == 0 {
+                &mut node.elem
+            } else {
+                link_lookup_mut(&mut node.next, index - 1)
+            }
+        }
+        None => unreachable!(),
+    }
+}
+
+#[requires(index <= link_len(link))]
+#[ensures(link_len(link) == old(link_len(link)) + 1)]
+#[ensures(snap(link_lookup(link, index)) === elem)]
+#[ensures(forall(|i: usize| (i < index) ==>
+             old(link_lookup(link, i)) === link_lookup(link, i)))]
+#[ensures(forall(|i: usize| (index <= i && i < old(link_len(link))) ==>
+             old(link_lookup(link, i)) === link_lookup(link, i + 1)))]
+fn link_insert<T, A: Allocator + Clone>(link: &mut Link<T, A>, index: usize, elem: T, alloc: &A) {
+    if index == 0 {
+        let new_node = Box::new_in(Node {
+            elem,
+            next: link.take(),
+        }, alloc.clone());
+        *link = Some(new_node);
+    } else {
+        match link {
+            Some(node) => link_insert(&mut node.next, index - 1, elem, alloc),
+            None => unreachable!(),
+        }
+    }
+}
+
+#[requires(index < link_len(link))]
+#[ensures(link_len(link) == old(link_len(link)) - 1)]
+#[ensures(result === old(snap(link_lookup(link, index))))]
+#[ensures(forall(|i: usize| (i < index) ==>
+             old(link_lookup(link, i)) === link_lookup(link, i)))]
+#[ensures(forall(|i: usize| (index < i && i < old(link_len(link))) ==>
+             old(link_lookup(link, i)) === link_lookup(link, i - 1)))]
+fn link_remove<T, A: Allocator>(link: &mut Link<T, A>, index: usize) -> T {
+    if index == 0 {
+        match link.take() {
+            Some(node) => {
+                let node = unbox_node(node);
+                *link = node.next;
+                node.elem
+            }
+            None => unreachable!(),
+        }
+    } else {
+        match link {
+            Some(node) => link_remove(&mut node.next, index - 1),
+            None => unreachable!(),
+        }
+    }
+}
+
+#[ensures(link_len(link) == old(link_len(link)) + old(link_len(&other)))]
+#[ensures(forall(|i: usize| (i < old(link_len(link))) ==>
+             old(link_lookup(link, i)) === link_lookup(link, i)))]
+#[ensures(forall(|i: usize| (i < old(link_len(&other))) ==>
+             old(link_lookup(&other, i)) === link_lookup(link, old(link_len(link)) + i)))]
+fn link_append<T, A: Allocator>(link: &mut Link<T, A>, other: Link<T, A>) {
+    match link {
+        Some(node) => link_append(&mut node.next, other),
+        None => *link = other,
+    }
+}
+
+#[requires(at <= link_len(link))]
+#[ensures(link_len(link) == at)]
+#[ensures(link_len(&result) == old(link_len(link)) - at)]
+#[ensures(forall(|i: usize| (i < at) ==>
+             old(link_lookup(link, i)) === link_lookup(link, i)))]
+#[ensures(forall(|i: usize| (i < link_len(&result)) ==>
+             old(link_lookup(link, at + i)) === link_lookup(&result, i)))]
+fn link_split_off<T, A: Allocator>(link: &mut Link<T, A>, at: usize) -> Link<T, A> {
+    if at == 0 {
+        link.take()
+    } else {
+        match link {
+            Some(node) => link_split_off(&mut node.next, at - 1),
+            None => unreachable!(),
+        }
+    }
+}
+
+/// Allocates a box for `node`, or hands `node` back if the allocation fails.
+#[trusted] // the contents of the allocated box are not visible to Prusti
+#[ensures(result.is_ok() ==> snap(&**peek_result_ref(&result)) === node)]
+#[ensures(result.is_err() ==> snap(peek_err_ref(&result)) === node)]
+fn try_box_node<T, A: Allocator>(node: Node<T, A>, alloc: A) -> Result<Box<Node<T, A>, A>, Node<T, A>> {
+    match Box::try_new_uninit_in(alloc) {
+        Ok(uninit) => Ok(Box::write(uninit, node)),
+        Err(AllocError) => Err(node),
+    }
+}
+
+/// Moves a node out of its box.
+/// Only the standard `Box` (used with the `allocator_api` feature) supports moving out with `*`.
+#[ensures(result === old(snap(&*node)))]
+#[allow(clippy::boxed_local)]
+fn unbox_node<T, A: Allocator>(node: Box<Node<T, A>, A>) -> Node<T, A> {
+    #[cfg(feature = "allocator_api")]
+    let node = *node;
+    #[cfg(not(feature = "allocator_api"))]
+    let node = Box::into_inner(node);
+    node
+}
+
+#[refine_trait_spec]
+impl<T> Default for List<T> {
+    #[ensures(result.len() == 0)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, A: Allocator + Clone> Drop for List<T, A> {
+    /// Unlinks the nodes one by one, so that dropping a long list
+    /// does not recurse through every `Box<Node<T, A>, A>` and overflow the stack.
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+        while let Some(mut node) = link {
+            link = node.next.take();
+        }
+        self.len = 0;
+    }
+}
+
+/// A borrowing iterator over the elements of a `List`, from the head to the tail.
+/// The k-th element it yields is `list.lookup(k)`.
+/// The invariants state that `next` is the tail of the list starting at `index`,
+/// which is what the trusted `next` method relies on.
+#[invariant(self.index <= self.list.len())]
+#[invariant(link_len(self.next) == self.list.len() - self.index)]
+#[invariant(forall(|i: usize| (i < link_len(self.next)) ==>
+    link_lookup(self.next, i) === self.list.lookup(self.index + i)))]
+pub struct Iter<'a, T, A: Allocator + Clone = Global> {
+    list: &'a List<T, A>,
+    next: &'a Link<T, A>,
+    index: usize,
+}
+
+impl<'a, T, A: Allocator + Clone> Iter<'a, T, A> {
+    /// The list this iterator walks over.
+    #[pure]
+    pub fn list(&self) -> &'a List<T, A> {
+        self.list
+    }
+
+    /// The number of elements yielded so far, i.e., the list index of the next element.
+    #[pure]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+#[refine_trait_spec]
+impl<'a, T, A: Allocator + Clone> Iterator for Iter<'a, T, A> {
+    type Item = &'a T;
+
+    #[trusted] // required due to unsupported reference in enum
+    #[ensures(snap(self.list()) === old(snap(self.list())))]
+    #[ensures(old(self.index()) >= self.list().len() ==>
+        result.is_none() &&
+        self.index() == old(self.index())
+    )]
+    #[ensures(old(self.index()) < self.list().len() ==>
+        result.is_some() &&
+        self.index() == old(self.index()) + 1 &&
+        snap(*peek_option_ref(&result)) === snap(self.list().lookup(old(self.index())))
+    )]
+    fn next(&mut self) -> Option<&'a T> {
+        match self.next {
+            Some(node) => {
+                self.next = &node.next;
+                self.index += 1;
+                Some(&node.elem)
+            }
+            None => None,
+        }
+    }
+}
+
+impl<'a, T, A: Allocator + Clone> IntoIterator for &'a List<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, A>;
+
+    fn into_iter(self) -> Iter<'a, T, A> {
+        self.iter()
+    }
+}
+
+/// A mutable iterator over the elements of a `List`, from the head to the tail.
+/// Elements that have not been yielded yet are left untouched; nothing is known about
+/// the yielded ones once the iterator expires (see `List::iter_mut`).
+/// The invariant states that `next` holds exactly the elements from `index` to `list_len`.
+#[invariant(self.index <= self.list_len)]
+#[invariant(self.index + self.remaining() == self.list_len)]
+pub struct IterMut<'a, T, A: Allocator + Clone = Global> {
+    next: Option<&'a mut Node<T, A>>,
+    index: usize,
+    list_len: usize,
+}
+
+impl<'a, T, A: Allocator + Clone> IterMut<'a, T, A> {
+    /// The number of elements yielded so far, i.e., the list index of the next element.
+    #[pure]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The length of the list this iterator walks over.
+    #[pure]
+    pub fn list_len(&self) -> usize {
+        self.list_len
+    }
+
+    /// The number of elements that have not been yielded yet.
+    #[pure]
+    #[trusted] // required due to unsupported reference in enum
+    fn remaining(&self) -> usize {
+        match &self.next {
+            Some(node) => 1 + link_len(&node.next),
+            None => 0,
+        }
+    }
+
+    /// The element at list index `index` among those that have not been yielded yet.
+    #[pure]
+    #[trusted] // required due to unsupported reference in enum
+    #[requires(self.index() <= index && index < self.list_len())]
+    pub fn lookup(&self, index: usize) -> &T {
+        match &self.next {
+            Some(node) => {
+                if index == self.index {
+                    &node.elem
+                } else {
+                    link_lookup(&node.next, index - self.index - 1)
+                }
+            }
+            None => unreachable!(),
+        }
+    }
+}
+
+#[refine_trait_spec]
+impl<'a, T, A: Allocator + Clone> Iterator for IterMut<'a, T, A> {
+    type Item = &'a mut T;
+
+    #[trusted] // required due to unsupported reference in enum
+    #[ensures(self.list_len() == old(self.list_len()))]
+    #[ensures(old(self.index()) >= self.list_len() ==>
+        result.is_none() &&
+        self.index() == old(self.index())
+    )]
+    #[ensures(old(self.index()) < self.list_len() ==>
+        result.is_some() &&
+        self.index() == old(self.index()) + 1 &&
+        snap(peek_option_deref_mut(&result)) === old(snap(self.lookup(self.index())))
+    )]
+    // the elements after the yielded one are still the same
+    #[ensures(forall(|i: usize| self.index() <= i && i < self.list_len() ==>
+        snap(self.lookup(i)) === old(snap(self.lookup(i)))))]
+    fn next(&mut self) -> Option<&'a mut T> {
+        match self.next.take() {
+            Some(node) => {
+                self.next = node.next.as_deref_mut();
+                self.index += 1;
+                Some(&mut node.elem)
+            }
+            None => None,
+        }
+    }
+}
+
+impl<'a, T, A: Allocator + Clone> IntoIterator for &'a mut List<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T, A>;
+
+    fn into_iter(self) -> IterMut<'a, T, A> {
+        self.iter_mut()
+    }
+}
+
+/// An owning iterator over the elements of a `List`, which pops them from the head.
+/// Created by `List::into_iter`.
+pub struct IntoIter<T, A: Allocator + Clone = Global>(List<T, A>);
+
+impl<T, A: Allocator + Clone> IntoIter<T, A> {
+    /// The elements that have not been yielded yet.
+    #[pure]
+    pub fn list(&self) -> &List<T, A> {
+        &self.0
+    }
+}
+
+#[refine_trait_spec]
+impl<T, A: Allocator + Clone> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    #[ensures(old(self.list().is_empty()) ==>
+        result.is_none() &&
+        self.list().is_empty()
+    )]
+    #[ensures(!old(self.list().is_empty()) ==>
+        self.list().head_removed(&old(snap(self.list()))) &&
+        result === Some(snap(old(snap(self.list())).lookup(0)))
+    )]
+    fn next(&mut self) -> Option<T> {
+        self.0.try_pop()
+    }
+}
+
+impl<T, A: Allocator + Clone> IntoIterator for List<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    #[ensures(snap(result.list()) === old(snap(&self)))]
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter(self)
+    }
+}
+
+/// Builds a list by pushing the items in iteration order,
+/// so the last item ends up at the head and the list is the reverse of the input.
+///
+/// Unverified: Prusti cannot reason about an arbitrary `IntoIterator`, so there is no contract
+/// on the contents of the result. Use `List::push_all` or `List::extend_from_slice` on a new list,
+/// which are fully verified, when the input is a `List` or a slice.
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = List::new();
+        list.extend(iter);
+        list
+    }
+}
+
+/// Pushes the items in iteration order, so they end up in reverse order in front of the old elements.
+///
+/// Unverified: Prusti cannot reason about an arbitrary `IntoIterator`, so the contract is trusted
+/// and only states that the old elements are shifted back by the number of new ones; it says nothing
+/// about the new elements. Use `List::push_all` or `List::extend_from_slice`, which are fully verified,
+/// when the input is a `List` or a slice.
+#[refine_trait_spec]
+impl<T, A: Allocator + Clone> Extend<T> for List<T, A> {
+    #[trusted] // iterating over an arbitrary `IntoIterator` is not supported by Prusti
+    #[ensures(self.len() >= old(self.len()))]
+    #[ensures(forall(|i: usize| (i < old(self.len())) ==>
+                 old(self.lookup(i)) === self.lookup(i + self.len() - old(self.len()))))]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+// `Copy` is already implied by `UniqueCheck`, but Prusti only accepts a pure function
+// returning `T` by value if the bound is stated directly.
+impl<T: UniqueCheck + Copy, A: Allocator + Clone> List<T, A> {
+
+    #[pure]
+    #[requires(index < self.len())]
+    pub fn lookup_copy(&self, index: usize) -> T {
+        *self.lookup(index)
+    }
+
+    predicate! {
+        // every element is disjoint from all the elements pushed before it,
+        // i.e., from all the elements that come after it in the list
+        // (with `UniqueCheck::overlaps_symmetric`, no two elements overlap in either direction)
+        pub fn all_disjoint(&self) -> bool {
+            forall(|i: usize, j: usize| (i < j && j < self.len()) ==> {
+                let earlier = self.lookup_copy(j);
+                !earlier.overlaps(&self.lookup_copy(i))
+            })
+        }
+    }
+
+    /// Pushes `elem` if it does not overlap any element in the list,
+    /// otherwise returns the index of the first overlapping element and leaves the list unchanged.
+    #[requires(self.all_disjoint())]
+    #[ensures(self.all_disjoint())]
+    #[ensures(result.is_ok() ==>
+        self.len() == old(self.len()) + 1 &&
+        snap(self.lookup(0)) === elem &&
+        forall(|i: usize| (i < old(self.len())) ==>
+            old(self.lookup(i)) === self.lookup(i + 1))
+    )]
+    #[ensures(result.is_err() ==>
+        self.len() == old(self.len()) &&
+        forall(|i: usize| (i < self.len()) ==>
+            old(self.lookup(i)) === self.lookup(i)) && {
+            let idx = peek_err(&result);
+            idx < self.len() && self.lookup_copy(idx).overlaps(&elem)
+        }
+    )]
+    pub fn push_unique(&mut self, elem: T) -> Result<(), usize> {
+        match self.elem_overlaps_in_list(elem, 0) {
+            Some(idx) => Err(idx),
+            None => {
+                self.push(elem);
+                Ok(())
+            }
+        }
+    }
+
+    #[requires(0 <= index && index <= self.len())]
+    #[ensures(result.is_some() ==> peek_option(&result) < self.len())]
+    #[ensures(result.is_some() ==> {
+            let idx = peek_option(&result);
+            let range = self.lookup_copy(idx);
+            range.overlaps(&elem)
+        }
+    )]
+    #[ensures(result.is_none() ==> 
+        forall(|i: usize| (index <= i && i < self.len()) ==> {
+            let range = self.lookup_copy(i);
+            !range.overlaps(&elem)
+        })
+    )]
+    pub(crate) fn elem_overlaps_in_list(&self, elem: T, index: usize) -> Option<usize> {
+        // A single walk over the nodes, instead of a `lookup_copy` per index.
+        let mut link = &self.head;
+        let mut i = 0;
+        while i < self.len() {
+            body_invariant!(i < self.len());
+            body_invariant!(link_len(link) == self.len() - i);
+            // `link` is the tail of the list starting at `i`
+            body_invariant!(forall(|j: usize| (j < link_len(link)) ==>
+                link_lookup(link, j) === self.lookup(i + j)));
+            // none of the elements checked so far overlap
+            body_invariant!(forall(|j: usize| (index <= j && j < i) ==> {
+                let range = self.lookup_copy(j);
+                !range.overlaps(&elem)
+            }));
+            match link {
+                Some(node) => {
+                    if index <= i && node.elem.overlaps(&elem) {
+                        return Some(i);
+                    }
+                    link = &node.next;
+                }
+                None => unreachable!(),
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Returns the indices of all elements that overlap `elem`, in ascending order.
+    /// The result is allocated with the allocator of `self`,
+    /// and an allocation failure is returned as an error instead of aborting.
+    #[ensures(result.is_ok() ==> forall(|k: usize| (k < peek_result_ref(&result).len()) ==> {
+            let idx = *peek_result_ref(&result).lookup(k);
+            idx < self.len() && self.lookup_copy(idx).overlaps(&elem)
+        })
+    )]
+    #[ensures(result.is_ok() ==> forall(|i: usize| (i < self.len()) ==>
+        (self.lookup_copy(i).overlaps(&elem) ==
+            exists(|k: usize| k < peek_result_ref(&result).len() && *peek_result_ref(&result).lookup(k) == i))
+    ))]
+    #[ensures(result.is_ok() ==> forall(|k: usize| (k + 1 < peek_result_ref(&result).len()) ==>
+        *peek_result_ref(&result).lookup(k) < *peek_result_ref(&result).lookup(k + 1)
+    ))]
+    pub fn overlaps_in_list_all(&self, elem: T) -> Result<List<usize, A>, AllocError> {
+        let mut overlaps = List::new_in(self.alloc.clone());
+        let mut link = &self.head;
+        let mut i = 0;
+        while i < self.len() {
+            body_invariant!(i < self.len());
+            body_invariant!(link_len(link) == self.len() - i);
+            // `link` is the tail of the list starting at `i`
+            body_invariant!(forall(|j: usize| (j < link_len(link)) ==>
+                link_lookup(link, j) === self.lookup(i + j)));
+            // the indices found so far are exactly the overlapping ones below `i`, in descending order
+            body_invariant!(forall(|k: usize| (k < overlaps.len()) ==> {
+                let idx = *overlaps.lookup(k);
+                idx < i && self.lookup_copy(idx).overlaps(&elem)
+            }));
+            body_invariant!(forall(|j: usize| (j < i) ==>
+                (self.lookup_copy(j).overlaps(&elem) ==
+                    exists(|k: usize| k < overlaps.len() && *overlaps.lookup(k) == j))
+            ));
+            body_invariant!(forall(|k: usize| (k + 1 < overlaps.len()) ==>
+                *overlaps.lookup(k) > *overlaps.lookup(k + 1)
+            ));
+            match link {
+                Some(node) => {
+                    if node.elem.overlaps(&elem) {
+                        if let Err((_, err)) = overlaps.try_push(i) {
+                            return Err(err);
+                        }
+                    }
+                    link = &node.next;
+                }
+                None => unreachable!(),
+            }
+            i += 1;
+        }
+        overlaps.reverse();
+        Ok(overlaps)
+    }
+}
+
+#[pure]
+fn link_len<T, A: Allocator>(link: &Link<T, A>) -> usize {
+    match link {
+        None => 0,
+        Some(node) => 1 + link_len(&node.next),
+    }
+}
+
+#[cfg(prusti)]
+mod prusti_tests {
+    use super::*;
+
+    fn _test_list(){
+        let mut list = List::new(); // create an new, empty list
+        prusti_assert!(list.is_empty() && list.len() == 0); // list should be empty
+
+        list.push(5);
+        list.push(10);
+        prusti_assert!(!list.is_empty() && list.len() == 2); // length correct
+
+        prusti_assert!(*list.lookup(0) == 10); // head is 10
+        prusti_assert!(*list.lookup(1) == 5); // 5 got pushed back correctly
+
+        let x = list.pop();
+        prusti_assert!(x == 10); // pop returns the value that was added last
+
+        match list.try_pop() {
+            Some(y) => assert!(y == 5),
+            None => unreachable!()
+        }
+
+        let z = list.try_pop();
+        prusti_assert!(list.is_empty() && list.len() == 0); // length correct
+        prusti_assert!(z.is_none()); // `try_pop` on an empty list should return `None`
+    }
+
+    fn _test_try_push() {
+        let mut list = List::new();
+        list.push(8);
+
+        match list.try_push(16) {
+            Ok(()) => {
+                prusti_assert!(list.len() == 2); // same as `push`
+                prusti_assert!(*list.lookup(0) == 16);
+                prusti_assert!(*list.lookup(1) == 8);
+            }
+            Err((elem, _)) => {
+                prusti_assert!(elem == 16); // the element is handed back
+                prusti_assert!(list.len() == 1); // and the list is unchanged
+                prusti_assert!(*list.lookup(0) == 8);
+            }
+        }
+    }
+
+    fn _test_peek() {
+        let mut list = List::new();
+        list.push(16);
+        prusti_assert!(list.peek() === list.lookup(0));
+        prusti_assert!(*list.peek() == 16);
+        
+        list.push(5);
+        prusti_assert!(*list.peek() == 5);
+
+        list.pop();
+        prusti_assert!(*list.peek() == 16);
+    }
+
+    fn _test_peek_mut() {
+        let mut list = List::new();
+        list.push(8);
+        list.push(16);
+
+        // Open a new scope using an opening bracket `{`
+        // `first` will get dropped at the closing bracket `}`
+        {
+            let first = list.peek_mut();
+            // `first` is a mutable reference to the first element of the list
+            // for as long as `first` is exists, `list` cannot be accessed
+            prusti_assert!(*first == 16);
+            *first = 5; // Write 5 to the first slot of the list
+            prusti_assert!(*first == 5);
+            // `first` gets dropped here, `list` can be accessed again
+        }
+        prusti_assert!(list.len() == 2);
+        prusti_assert!(*list.lookup(0) == 5); // slot 0 is now `5`
+        prusti_assert!(*list.lookup(1) == 8); // slot 1 is unchanged
+    }
+
+    fn _test_overlaps_symmetric<T: UniqueCheck + Copy, A: Allocator + Clone>(list: &List<T, A>, elem: T) {
+        let overlap = list.elem_overlaps_in_list(elem, 0);
+        if overlap.is_none() && !list.is_empty() {
+            let first = list.lookup_copy(0);
+            prusti_assert!(!first.overlaps(&elem)); // what `elem_overlaps_in_list` tells us
+            first.overlaps_symmetric(&elem);
+            prusti_assert!(!elem.overlaps(&first)); // the reverse direction follows from symmetry
+        }
+    }
+
+    fn _test_empty_overlaps_nothing<T: UniqueCheck + Copy, A: Allocator + Clone>(list: &List<T, A>, elem: T) {
+        if elem.is_empty() {
+            let overlap = list.elem_overlaps_in_list(elem, 0);
+            prusti_assert!(overlap.is_none()); // an empty element never conflicts with the list
+        }
+    }
+
+    fn _test_try_peek() {
+        let mut list = List::new();
+        let none = list.try_peek();
+        prusti_assert!(none.is_none()); // an empty list has no head
+
+        list.push(16);
+        let head = list.try_peek();
+        prusti_assert!(head.is_some());
+        prusti_assert!(*peek_option_deref(&head) == 16);
+    }
+
+    fn _test_try_peek_mut() {
+        let mut list = List::new();
+        list.push(8);
+        list.push(16);
+
+        {
+            let first = list.try_peek_mut();
+            prusti_assert!(first.is_some());
+            prusti_assert!(*peek_option_deref_mut(&first) == 16);
+            // `first` gets dropped here, `list` can be accessed again
+        }
+        prusti_assert!(list.len() == 2);
+        prusti_assert!(*list.lookup(0) == 16); // unchanged, since nothing was written
+        prusti_assert!(*list.lookup(1) == 8); // slot 1 is unchanged
+    }
+
+    fn _test_iter() {
+        let mut list = List::new();
+        list.push(8);
+        list.push(16);
+
+        let mut iter = list.iter();
+        prusti_assert!(iter.index() == 0);
+
+        let first = iter.next();
+        prusti_assert!(first.is_some());
+        prusti_assert!(**peek_option_ref(&first) == 16); // first item is `lookup(0)`
+
+        let second = iter.next();
+        prusti_assert!(second.is_some());
+        prusti_assert!(**peek_option_ref(&second) == 8); // second item is `lookup(1)`
+
+        let third = iter.next();
+        prusti_assert!(third.is_none()); // the iterator is exhausted after `len()` items
+    }
+
+    fn _test_insert_remove() {
+        let mut list = List::new();
+        list.push(3);
+        list.push(1);
+
+        list.insert(1, 2); // insert between the two elements
+        prusti_assert!(list.len() == 3);
+        prusti_assert!(*list.lookup(0) == 1); // elements before `index` are unchanged
+        prusti_assert!(*list.lookup(1) == 2);
+        prusti_assert!(*list.lookup(2) == 3); // elements from `index` on are shifted back
+
+        list.insert(3, 4); // inserting at `len()` appends
+        prusti_assert!(*list.lookup(3) == 4);
+
+        let x = list.remove(1);
+        prusti_assert!(x == 2);
+        prusti_assert!(list.len() == 3);
+        prusti_assert!(*list.lookup(0) == 1);
+        prusti_assert!(*list.lookup(1) == 3); // later elements are shifted forwards
+        prusti_assert!(*list.lookup(2) == 4);
+    }
+
+    fn _test_append_split_off() {
+        let mut list = List::new();
+        list.push(2);
+        list.push(1);
+
+        let mut other = List::new();
+        other.push(4);
+        other.push(3);
+
+        list.append(&mut other);
+        prusti_assert!(other.len() == 0); // `other` is left empty
+        prusti_assert!(list.len() == 4);
+        prusti_assert!(*list.lookup(0) == 1 && *list.lookup(1) == 2);
+        prusti_assert!(*list.lookup(2) == 3 && *list.lookup(3) == 4);
+
+        let tail = list.split_off(1);
+        prusti_assert!(list.len() == 1 && *list.lookup(0) == 1);
+        prusti_assert!(tail.len() == 3);
+        prusti_assert!(*tail.lookup(0) == 2 && *tail.lookup(2) == 4);
+    }
+
+    fn _test_reverse() {
+        let mut list = List::new();
+        list.push(3);
+        list.push(2);
+        list.push(1);
+
+        list.reverse();
+        prusti_assert!(list.len() == 3);
+        prusti_assert!(*list.lookup(0) == 3);
+        prusti_assert!(*list.lookup(1) == 2);
+        prusti_assert!(*list.lookup(2) == 1);
+    }
+
+    fn _test_push_all() {
+        let mut list = List::new();
+        list.push(1);
+
+        let mut other = List::new();
+        other.push(2);
+        other.push(3);
+
+        list.push_all(other);
+        prusti_assert!(list.len() == 3);
+        prusti_assert!(*list.lookup(0) == 2); // the tail of `other` ends up at the head
+        prusti_assert!(*list.lookup(1) == 3);
+        prusti_assert!(*list.lookup(2) == 1); // the old elements are shifted back
+    }
+
+    fn _test_extend_from_slice() {
+        let mut list = List::new();
+        list.push(1);
+
+        list.extend_from_slice(&[2, 3]);
+        prusti_assert!(list.len() == 3);
+        prusti_assert!(*list.lookup(0) == 3); // the last item ends up at the head
+        prusti_assert!(*list.lookup(1) == 2);
+        prusti_assert!(*list.lookup(2) == 1); // the old elements are shifted back
+    }
+
+    fn _test_into_iter() {
+        let mut list = List::new();
+        list.push(8);
+        list.push(16);
+
+        let mut iter = list.into_iter();
+        let first = iter.next();
+        prusti_assert!(first === Some(16)); // items are popped from the head
+        let second = iter.next();
+        prusti_assert!(second === Some(8));
+        let third = iter.next();
+        prusti_assert!(third.is_none());
+    }
+
+    fn _test_iter_mut() {
+        let mut list = List::new();
+        list.push(8);
+        list.push(16);
+
+        {
+            let mut iter = list.iter_mut();
+            prusti_assert!(iter.index() == 0 && iter.list_len() == 2);
+
+            let first = iter.next(); // may be used to change slot 0
+            prusti_assert!(first.is_some());
+            prusti_assert!(*peek_option_deref_mut(&first) == 16); // it is the head of the list
+            prusti_assert!(iter.index() == 1);
+            // `iter` gets dropped here, `list` can be accessed again
+        }
+        prusti_assert!(list.len() == 2); // length unchanged
+        prusti_assert!(*list.lookup(1) == 8); // slot 1 was never yielded, so it is unchanged
+    }
+
+    fn _test_lookup_mut() {
+        let mut list = List::new();
+        list.push(8);
+        list.push(16);
+        list.push(32);
+
+        let elem = list.lookup_mut(1);
+        *elem = 17;
+        prusti_assert!(list.len() == 3); // length unchanged
+        prusti_assert!(*list.lookup(1) == 17); // the new value is known
+        prusti_assert!(*list.lookup(0) == 32 && *list.lookup(2) == 8); // the others are unchanged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::alloc::Layout;
+    use core::ptr::NonNull;
+
+    /// An allocator that is always out of memory.
+    #[derive(Clone)]
+    struct FailingAllocator;
+
+    unsafe impl Allocator for FailingAllocator {
+        fn allocate(&self, _layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            Err(AllocError)
+        }
+
+        unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+    }
+
+    #[test]
+    fn try_push_hands_back_elem_on_alloc_failure() {
+        let mut list = List::new_in(FailingAllocator);
+        match list.try_push(5) {
+            Ok(()) => panic!("allocation should have failed"),
+            Err((elem, _)) => assert_eq!(elem, 5),
+        }
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn drop_long_list() {
+        let mut list = List::new();
+        for i in 0..1_000_000 {
+            list.push(i);
+        }
+        drop(list); // must not overflow the stack
+    }
+}
@@ -1,12 +0,0 @@
-#![allow(dead_code)]
-#![allow(unused_imports)]
-
-extern crate prusti_contracts;
-
-mod external_spec;
-mod range_inclusive;
-// mod with_concrete_type;
-mod with_generic_type;
-
-fn main() {
-}
@@ -0,0 +1,122 @@
+use prusti_contracts::*;
+
+use crate::{
+    external_spec::trusted_option::*,
+    external_spec::trusted_result::*,
+    range_inclusive::*,
+    range_trait::*,
+};
+
+/// A struct representing an unallocated region in memory.
+/// Its functions are formally verified to prevent range overlaps between chunks.
+///
+/// A range is either the explicit empty range from `empty()`, or holds bounds with `start <= end`.
+/// `new` checks the bounds, so a range with `start > end` cannot be created.
+#[derive(Copy, Clone, PartialEq, Eq)]
+#[invariant(self.0.is_some() ==> {
+    !peek_option_ref(&self.0).is_empty()
+})]
+pub struct GenericRange<U: Copy + PartialOrd>(Option<RangeInclusive<U>>);
+
+/// The common case of a range of plain `usize` indices, e.g., frame or page numbers.
+pub type Range = GenericRange<usize>;
+
+/// The error returned when creating a range from bounds that do not form one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RangeError {
+    /// The start is after the end. Use `GenericRange::empty()` for an empty range.
+    StartAfterEnd,
+}
+
+impl<U: Copy + PartialOrd> GenericRange<U> {
+    /// Returns the range `start..=end`, or an error if `start > end`.
+    #[ensures(start <= end ==> result.is_ok() && {
+        let range = peek_result(&result);
+        !range.is_empty() && *range.start() === start && *range.end() === end
+    })]
+    #[ensures(!(start <= end) ==> result === Err(RangeError::StartAfterEnd))]
+    pub fn new(start: U, end: U) -> Result<Self, RangeError> {
+        if start <= end {
+            Ok(GenericRange(Some(RangeInclusive::new(start, end))))
+        } else {
+            Err(RangeError::StartAfterEnd)
+        }
+    }
+
+    /// Returns the empty range, which overlaps nothing.
+    #[ensures(result.is_empty())]
+    pub const fn empty() -> Self {
+        GenericRange(None)
+    }
+
+    /// Returns the first index of the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty; the verifier rules this out with the precondition.
+    /// Use `to_range_inclusive` to handle the empty range without panicking.
+    #[pure]
+    #[requires(!self.is_empty())]
+    pub fn start(&self) -> &U {
+        peek_option_ref(&self.0).start()
+    }
+
+    /// Returns the last index of the range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty; the verifier rules this out with the precondition.
+    /// Use `to_range_inclusive` to handle the empty range without panicking.
+    #[pure]
+    #[requires(!self.is_empty())]
+    pub fn end(&self) -> &U {
+        peek_option_ref(&self.0).end()
+    }
+
+    /// Returns the bounds as a `RangeInclusive`, or `None` for the empty range.
+    #[pure]
+    pub fn to_range_inclusive(&self) -> Option<RangeInclusive<U>> {
+        self.0
+    }
+}
+
+impl<U: Copy + PartialOrd> UniqueCheck for GenericRange<U> {
+    /// Two ranges overlap if the larger of the starts is at most the smaller of the ends.
+    /// For non-empty ranges that is the same as each range starting no later than the other one ends,
+    /// which is how it is written here so that it stays symmetric when `U` is only partially ordered.
+    #[pure]
+    fn overlaps(&self, other: &Self) -> bool {
+        !self.is_empty() && !other.is_empty()
+            && *self.start() <= *other.end() && *other.start() <= *self.end()
+    }
+
+    #[pure]
+    fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    // Both lemmas are proven by unfolding `overlaps` and `is_empty`,
+    // and reflexivity also uses the type invariant `start <= end`.
+    fn overlaps_symmetric(&self, _other: &Self) {}
+
+    fn overlaps_reflexive(&self) {}
+}
+
+#[cfg(prusti)]
+mod prusti_tests {
+    use super::*;
+
+    fn _test_new_and_empty() {
+        let a = Range::new(2, 5);
+        prusti_assert!(a.is_ok());
+        let a = a.unwrap();
+        prusti_assert!(*a.start() == 2 && *a.end() == 5);
+
+        let b = Range::new(5, 2);
+        prusti_assert!(b === Err(RangeError::StartAfterEnd)); // the bounds are checked
+
+        let empty = Range::empty();
+        prusti_assert!(empty.is_empty());
+        prusti_assert!(!empty.overlaps(&a) && !a.overlaps(&empty)); // the empty range overlaps nothing
+    }
+}
@@ -2,6 +2,10 @@
 
 use prusti_contracts::*;
 
+use crate::external_spec::trusted_option::*;
+use crate::external_spec::trusted_partial_ord::total_order;
+use crate::step::Step;
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct RangeInclusive<Idx: Clone + PartialOrd> {
     start: Idx,
@@ -25,7 +29,362 @@ impl<Idx: Clone + PartialOrd> RangeInclusive<Idx> {
         &self.end
     }
 
+    /// Returns true if the range contains no indices, i.e., if `start > end`
+    /// (or the bounds are not comparable).
+    #[pure]
+    #[ensures(result == !(*self.start() <= *self.end()))]
     pub fn is_empty(&self) -> bool {
         !(self.start <= self.end)
     }
+
+    /// Returns true if `idx` lies within the range, including both ends.
+    #[pure]
+    pub fn contains(&self, idx: &Idx) -> bool {
+        *self.start() <= *idx && *idx <= *self.end()
+    }
+
+    /// Returns true if every index in `other` also lies within `self`.
+    /// An empty `other` is contained in every range.
+    #[pure]
+    pub fn contains_range(&self, other: &Self) -> bool {
+        other.is_empty()
+            || (*self.start() <= *other.start() && *other.end() <= *self.end())
+    }
+}
+
+/// Taking the larger start and the smaller end only gives the right bounds for a total order,
+/// so this operation needs `Ord` rather than `PartialOrd`.
+impl<Idx: Copy + Ord> RangeInclusive<Idx> {
+    /// Returns the indices that lie within both ranges, or `None` if there are none.
+    #[ensures(result.is_some() ==> !peek_option(&result).is_empty())]
+    #[ensures(result.is_some() ==> forall(|idx: Idx|
+        peek_option(&result).contains(&idx) == (self.contains(&idx) && other.contains(&idx))
+    ))]
+    #[ensures(result.is_none() ==> forall(|idx: Idx|
+        !(self.contains(&idx) && other.contains(&idx))
+    ))]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        total_order::<Idx>();
+        let start = if self.start > other.start { self.start } else { other.start };
+        let end = if self.end < other.end { self.end } else { other.end };
+        if start <= end {
+            Some(RangeInclusive::new(start, end))
+        } else {
+            None
+        }
+    }
+}
+
+/// The sizes are counted with index arithmetic, so they are provided for `usize` indices,
+/// where that arithmetic can be verified.
+impl RangeInclusive<usize> {
+    /// Returns the number of indices in the range.
+    /// A `u128` can hold the size of every `usize` range, including `0..=usize::MAX`.
+    #[pure]
+    #[ensures(self.is_empty() ==> result == 0)]
+    #[ensures(!self.is_empty() ==> result == (*self.end() - *self.start()) as u128 + 1)]
+    #[ensures(result <= usize::MAX as u128 + 1)]
+    pub fn size_in_units_u128(&self) -> u128 {
+        if self.start <= self.end {
+            // neither `end - start` nor the `+ 1` can overflow (see the last postcondition)
+            (self.end - self.start) as u128 + 1
+        } else {
+            0
+        }
+    }
+
+    /// Returns the number of indices in the range,
+    /// or `None` if it does not fit in a `usize`, which is only the case for `0..=usize::MAX`.
+    #[ensures(result.is_some() == (self.size_in_units_u128() <= usize::MAX as u128))]
+    #[ensures(result.is_some() ==> peek_option(&result) as u128 == self.size_in_units_u128())]
+    // `check_overflows` is off, so the bounds of the arithmetic are stated explicitly
+    #[ensures(!self.is_empty() ==> (result.is_some() == (*self.end() - *self.start() < usize::MAX)))]
+    #[ensures(!self.is_empty() && result.is_some() ==>
+        peek_option(&result) == *self.end() - *self.start() + 1)]
+    pub fn size_in_units(&self) -> Option<usize> {
+        if self.start > self.end {
+            return Some(0);
+        }
+        // `end - start` cannot underflow, and the `+ 1` is only done when it cannot overflow
+        prusti_assert!(self.start <= self.end);
+        let diff = self.end - self.start;
+        if diff == usize::MAX {
+            None
+        } else {
+            prusti_assert!(diff < usize::MAX);
+            Some(diff + 1)
+        }
+    }
+}
+
+/// The operations below need to step to the next or previous index at the inclusive ends,
+/// which `Step` does without overflowing at the smallest or largest index.
+impl<Idx: Step> RangeInclusive<Idx> {
+    /// Returns the smallest range covering both ranges if they overlap or are directly adjacent,
+    /// i.e., if the union has no gap. An empty range adds nothing to the union.
+    #[ensures(result.is_some() ==> forall(|idx: Idx|
+        peek_option(&result).contains(&idx) == (self.contains(&idx) || other.contains(&idx))
+    ))]
+    // otherwise, some index between the two ranges lies in neither of them
+    #[ensures(result.is_none() ==>
+        !self.is_empty() && !other.is_empty() && exists(|idx: Idx|
+            ((*self.end() < idx && idx < *other.start()) || (*other.end() < idx && idx < *self.start()))
+                && !self.contains(&idx) && !other.contains(&idx)
+        )
+    )]
+    pub fn union_if_adjacent_or_overlapping(&self, other: &Self) -> Option<Self> {
+        total_order::<Idx>();
+        if other.is_empty() {
+            return Some(*self);
+        }
+        if self.is_empty() {
+            return Some(*other);
+        }
+        if Self::has_gap(&self.end, &other.start) || Self::has_gap(&other.end, &self.start) {
+            return None;
+        }
+        let start = if self.start < other.start { self.start } else { other.start };
+        let end = if self.end > other.end { self.end } else { other.end };
+        Some(RangeInclusive::new(start, end))
+    }
+
+    /// Returns true if some index lies strictly between `end` and `start`,
+    /// i.e., if the index after `end` still lies before `start`.
+    #[pure]
+    fn has_gap(end: &Idx, start: &Idx) -> bool {
+        *end < *start && end.forward_checked().is_some() && peek_option(&end.forward_checked()) < *start
+    }
+
+    /// Returns the indices of `self` that do not lie within `other`.
+    /// Removing `other` can split `self` in two, so the result holds the part below `other`
+    /// and the part above it; each is `None` if it would be empty.
+    /// An empty `other` removes nothing, and all of `self` is returned as the first part.
+    #[ensures(result.0.is_some() ==> {
+        let below = peek_option(&result.0);
+        !below.is_empty() && (!other.is_empty() ==> *below.end() < *other.start())
+    })]
+    #[ensures(result.1.is_some() ==> {
+        let above = peek_option(&result.1);
+        !above.is_empty() && *other.end() < *above.start()
+    })]
+    #[ensures(forall(|idx: Idx|
+        ((result.0.is_some() && peek_option(&result.0).contains(&idx))
+            || (result.1.is_some() && peek_option(&result.1).contains(&idx)))
+        == (self.contains(&idx) && !other.contains(&idx))
+    ))]
+    pub fn difference(&self, other: &Self) -> (Option<Self>, Option<Self>) {
+        total_order::<Idx>();
+        if self.is_empty() {
+            return (None, None);
+        }
+        if other.is_empty() || self.end < other.start {
+            return (Some(*self), None);
+        }
+        if other.end < self.start {
+            return (None, Some(*self));
+        }
+        let below = if self.start < other.start {
+            match other.start.backward_checked() {
+                Some(prev) => Some(RangeInclusive::new(self.start, prev)),
+                None => unreachable!(), // `self.start` lies before `other.start`
+            }
+        } else {
+            None
+        };
+        let above = if other.end < self.end {
+            match other.end.forward_checked() {
+                Some(next) => Some(RangeInclusive::new(next, self.end)),
+                None => unreachable!(), // `self.end` lies after `other.end`
+            }
+        } else {
+            None
+        };
+        (below, above)
+    }
+
+    /// Returns an iterator over the indices of the range in ascending order, `start` and `end` included.
+    /// An empty range yields nothing.
+    #[ensures(self.is_empty() ==> result.next.is_none())]
+    #[ensures(!self.is_empty() ==> result.next === Some(*self.start()))]
+    #[ensures(result.end === *self.end())]
+    pub fn iter(&self) -> Indices<Idx> {
+        let next = if self.is_empty() { None } else { Some(self.start) };
+        Indices { next, end: self.end }
+    }
+}
+
+/// An iterator over the indices of a `RangeInclusive`, created by `RangeInclusive::iter`.
+///
+/// The next index is kept as an `Option`, and the iterator only steps forwards while it is below `end`,
+/// so a range ending at the largest index, e.g., `usize::MAX`, never overflows.
+#[invariant(self.next.is_some() ==> peek_option(&self.next) <= self.end)]
+pub struct Indices<Idx: Step> {
+    next: Option<Idx>,
+    end: Idx,
+}
+
+#[refine_trait_spec]
+impl<Idx: Step> Iterator for Indices<Idx> {
+    type Item = Idx;
+
+    #[ensures(old(self.next.is_none()) ==> result.is_none() && self.next.is_none())]
+    #[ensures(old(self.next.is_some()) ==> result === old(self.next))]
+    #[ensures(self.end === old(self.end))]
+    #[ensures(result.is_some() && self.next.is_some() ==> peek_option(&result) < peek_option(&self.next))]
+    // the next index is the one directly after `result`, so no index of the range is skipped
+    #[ensures(old(self.next.is_some() && peek_option(&self.next) < self.end) ==>
+        self.next === peek_option(&result).forward_checked()
+    )]
+    #[ensures(result.is_some() && self.next.is_some() ==> forall(|idx: Idx|
+        !(peek_option(&result) < idx && idx < peek_option(&self.next))
+    ))]
+    // iteration only stops after yielding an index that is not below `end`
+    #[ensures(old(self.next.is_some() && !(peek_option(&self.next) < self.end)) ==>
+        self.next.is_none() && !(peek_option(&result) < self.end)
+    )]
+    fn next(&mut self) -> Option<Idx> {
+        // with `current < end`, the least-successor law of `forward_checked` gives `next <= end`,
+        // which re-establishes the invariant
+        total_order::<Idx>();
+        if let Some(current) = self.next {
+            self.next = if current < self.end { current.forward_checked() } else { None };
+            Some(current)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(prusti)]
+mod prusti_tests {
+    use super::*;
+
+    fn _test_iter() {
+        let range = RangeInclusive::new(usize::MAX - 1, usize::MAX);
+        let mut iter = range.iter();
+
+        let a = iter.next();
+        prusti_assert!(a === Some(usize::MAX - 1));
+        let b = iter.next();
+        prusti_assert!(b === Some(usize::MAX)); // the last index is yielded without overflowing
+        let c = iter.next();
+        prusti_assert!(c.is_none());
+
+        let empty = RangeInclusive::new(1usize, 0);
+        let mut iter = empty.iter();
+        let d = iter.next();
+        prusti_assert!(d.is_none()); // an empty range yields nothing
+    }
+
+    fn _test_size_of_full_range() {
+        let all = RangeInclusive::new(0, usize::MAX);
+        let size = all.size_in_units();
+        prusti_assert!(size.is_none()); // does not fit in a `usize`
+        prusti_assert!(all.size_in_units_u128() == usize::MAX as u128 + 1);
+
+        let last = RangeInclusive::new(usize::MAX, usize::MAX);
+        let size = last.size_in_units();
+        prusti_assert!(size === Some(1));
+    }
+
+    fn _test_union_difference_at_bounds() {
+        let all = RangeInclusive::new(0, usize::MAX);
+        let first = RangeInclusive::new(0, 0);
+        let last = RangeInclusive::new(usize::MAX, usize::MAX);
+
+        let (below, above) = all.difference(&first);
+        prusti_assert!(below.is_none() && above.is_some());
+        prusti_assert!(peek_option(&above).contains(&1) && !peek_option(&above).contains(&0));
+
+        let (below, above) = all.difference(&last);
+        prusti_assert!(below.is_some() && above.is_none());
+        prusti_assert!(peek_option(&below).contains(&(usize::MAX - 1)) && !peek_option(&below).contains(&usize::MAX));
+
+        let rest = RangeInclusive::new(0, usize::MAX - 1);
+        let union = rest.union_if_adjacent_or_overlapping(&last);
+        prusti_assert!(union.is_some() && peek_option(&union).contains(&usize::MAX)); // adjacent at the top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct Frame(usize);
+
+    crate::impl_step_for_newtype!(Frame);
+
+    #[test]
+    fn iter_yields_every_index_up_to_max() {
+        let range = RangeInclusive::new(Frame(usize::MAX - 2), Frame(usize::MAX));
+        let frames: Vec<Frame> = range.iter().collect();
+        assert_eq!(frames, [Frame(usize::MAX - 2), Frame(usize::MAX - 1), Frame(usize::MAX)]);
+
+        assert_eq!(RangeInclusive::new(3u8, 2).iter().count(), 0);
+        assert_eq!(RangeInclusive::new(0u8, u8::MAX).iter().count(), 256);
+    }
+
+    #[test]
+    fn intersection_of_newtypes() {
+        let a = RangeInclusive::new(Frame(0), Frame(9));
+        let b = RangeInclusive::new(Frame(5), Frame(14));
+        assert!(a.intersection(&b) == Some(RangeInclusive::new(Frame(5), Frame(9))));
+        assert!(a.intersection(&RangeInclusive::new(Frame(10), Frame(14))).is_none());
+    }
+
+    #[test]
+    fn size_of_full_range() {
+        let all = RangeInclusive::new(0, usize::MAX);
+        assert_eq!(all.size_in_units(), None);
+        assert_eq!(all.size_in_units_u128(), usize::MAX as u128 + 1);
+
+        let last = RangeInclusive::new(usize::MAX, usize::MAX);
+        assert_eq!(last.size_in_units(), Some(1));
+        assert_eq!(last.size_in_units_u128(), 1);
+        assert_eq!(RangeInclusive::new(1, 0).size_in_units(), Some(0));
+    }
+
+    #[test]
+    fn union_and_difference_of_newtypes() {
+        let a = RangeInclusive::new(Frame(0), Frame(9));
+        let b = RangeInclusive::new(Frame(10), Frame(19));
+        assert!(a.union_if_adjacent_or_overlapping(&b) == Some(RangeInclusive::new(Frame(0), Frame(19))));
+        assert!(a.union_if_adjacent_or_overlapping(&RangeInclusive::new(Frame(11), Frame(19))).is_none());
+
+        let hole = RangeInclusive::new(Frame(3), Frame(5));
+        assert!(a.difference(&hole) == (
+            Some(RangeInclusive::new(Frame(0), Frame(2))),
+            Some(RangeInclusive::new(Frame(6), Frame(9))),
+        ));
+    }
+
+    #[test]
+    fn difference_at_bounds() {
+        let max = usize::MAX;
+        let all = RangeInclusive::new(0, max);
+        let r = RangeInclusive::new;
+
+        assert!(all.difference(&all) == (None, None));
+        assert!(all.difference(&r(0, 0)) == (None, Some(r(1, max))));
+        assert!(all.difference(&r(max, max)) == (Some(r(0, max - 1)), None));
+        assert!(all.difference(&r(1, max - 1)) == (Some(r(0, 0)), Some(r(max, max))));
+        assert!(r(5, 10).difference(&r(0, 0)) == (None, Some(r(5, 10))));
+        assert!(r(0, 0).difference(&r(max, max)) == (Some(r(0, 0)), None));
+        assert!(all.difference(&r(1, 0)) == (Some(all), None)); // an empty range removes nothing
+    }
+
+    #[test]
+    fn union_at_bounds() {
+        let max = usize::MAX;
+        let r = RangeInclusive::new;
+
+        assert!(r(0, 4).union_if_adjacent_or_overlapping(&r(5, 9)) == Some(r(0, 9)));
+        assert!(r(0, 4).union_if_adjacent_or_overlapping(&r(6, 9)).is_none());
+        assert!(r(max, max).union_if_adjacent_or_overlapping(&r(0, max - 1)) == Some(r(0, max)));
+        assert!(r(max, max).union_if_adjacent_or_overlapping(&r(0, max - 2)).is_none());
+        assert!(r(0, 0).union_if_adjacent_or_overlapping(&r(1, 0)) == Some(r(0, 0)));
+    }
 }
@@ -0,0 +1,24 @@
+use prusti_contracts::*;
+
+pub trait UniqueCheck: Copy + PartialEq {
+    /// Returns true if the two elements have some part in common.
+    /// An empty element overlaps nothing, not even itself.
+    #[pure]
+    #[ensures((self.is_empty() || other.is_empty()) ==> !result)]
+    fn overlaps(&self, other: &Self) -> bool;
+
+    /// Returns true if the element covers nothing.
+    #[pure]
+    fn is_empty(&self) -> bool;
+
+    // The laws of `overlaps` are stated as lemmas: every implementation has to prove the postcondition,
+    // and calling a lemma makes the law available to the verifier at the call site.
+
+    /// Lemma: `overlaps` is symmetric.
+    #[ensures(self.overlaps(other) == other.overlaps(self))]
+    fn overlaps_symmetric(&self, other: &Self);
+
+    /// Lemma: every non-empty element overlaps itself.
+    #[ensures(!self.is_empty() ==> self.overlaps(self))]
+    fn overlaps_reflexive(&self);
+}
@@ -0,0 +1,127 @@
+//! A trait for index types that can be stepped through one at a time, like the unstable `core::iter::Step`.
+//! It is what `RangeInclusive::iter` needs to visit every index of a range.
+
+use prusti_contracts::*;
+
+use crate::external_spec::trusted_option::*;
+
+/// The indices are totally ordered (`Ord`), so that the laws below determine the next index,
+/// and the verifier can use them together with `total_order`.
+pub trait Step: Copy + Ord {
+    /// Returns the index directly after `self`, or `None` if `self` is the largest index.
+    /// No index lies between `self` and the result, so stepping never skips an index.
+    #[pure]
+    #[ensures(result.is_some() ==> *self < peek_option(&result))]
+    // the result is the least index after `self`
+    #[ensures(result.is_some() ==> forall(|idx: Self|
+        *self < idx ==> peek_option(&result) <= idx
+    ))]
+    #[ensures(result.is_some() ==> forall(|idx: Self|
+        !(*self < idx && idx < peek_option(&result))
+    ))]
+    #[ensures(result.is_none() ==> forall(|idx: Self| !(*self < idx)))]
+    fn forward_checked(&self) -> Option<Self>;
+
+    /// Returns the index directly before `self`, or `None` if `self` is the smallest index.
+    /// No index lies between the result and `self`, so stepping never skips an index.
+    #[pure]
+    #[ensures(result.is_some() ==> peek_option(&result) < *self)]
+    // the result is the greatest index before `self`
+    #[ensures(result.is_some() ==> forall(|idx: Self|
+        idx < *self ==> idx <= peek_option(&result)
+    ))]
+    #[ensures(result.is_some() ==> forall(|idx: Self|
+        !(peek_option(&result) < idx && idx < *self)
+    ))]
+    #[ensures(result.is_none() ==> forall(|idx: Self| !(idx < *self)))]
+    fn backward_checked(&self) -> Option<Self>;
+}
+
+macro_rules! impl_step_for_int {
+    ($($t:ty),*) => {
+        $(
+            #[refine_trait_spec]
+            impl Step for $t {
+                // compared against `MAX` first, so the `+ 1` cannot overflow
+                #[ensures(*self < <$t>::MAX ==> result === Some(*self + 1))]
+                #[ensures(*self == <$t>::MAX ==> result.is_none())]
+                #[pure]
+                fn forward_checked(&self) -> Option<Self> {
+                    if *self < <$t>::MAX {
+                        Some(*self + 1)
+                    } else {
+                        None
+                    }
+                }
+
+                // compared against `MIN` first, so the `- 1` cannot overflow
+                #[ensures(*self > <$t>::MIN ==> result === Some(*self - 1))]
+                #[ensures(*self == <$t>::MIN ==> result.is_none())]
+                #[pure]
+                fn backward_checked(&self) -> Option<Self> {
+                    if *self > <$t>::MIN {
+                        Some(*self - 1)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_step_for_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Implements `Step` for a newtype index, e.g., `struct Frame(usize)`, by stepping its only field.
+///
+/// The newtype must be `Copy` and derive `PartialOrd` and `Ord`, so that it is ordered like its field.
+/// The verifier cannot see that derived ordering, which is why the implementation is trusted.
+#[macro_export]
+macro_rules! impl_step_for_newtype {
+    ($($t:ident),*) => {
+        $(
+            impl $crate::Step for $t {
+                #[$crate::__prusti_contracts::pure]
+                #[$crate::__prusti_contracts::trusted]
+                fn forward_checked(&self) -> Option<Self> {
+                    $crate::Step::forward_checked(&self.0).map($t)
+                }
+
+                #[$crate::__prusti_contracts::pure]
+                #[$crate::__prusti_contracts::trusted]
+                fn backward_checked(&self) -> Option<Self> {
+                    $crate::Step::backward_checked(&self.0).map($t)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(prusti)]
+mod prusti_tests {
+    use super::*;
+
+    #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    struct Frame(usize);
+
+    impl_step_for_newtype!(Frame);
+
+    fn _test_forward_checked() {
+        let next = 5usize.forward_checked();
+        prusti_assert!(next === Some(6));
+
+        let last = usize::MAX.forward_checked();
+        prusti_assert!(last.is_none()); // stepping past the largest index does not overflow
+
+        let prev = 5usize.backward_checked();
+        prusti_assert!(prev === Some(4));
+
+        let first = 0usize.backward_checked();
+        prusti_assert!(first.is_none()); // stepping before the smallest index does not underflow
+
+        let frame = Frame(5);
+        if let Some(next) = frame.forward_checked() {
+            prusti_assert!(frame < next); // a newtype steps forwards like its field
+        }
+    }
+}